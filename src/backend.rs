@@ -0,0 +1,133 @@
+use rust_bert::pipelines::sequence_classification::{SequenceClassificationConfig, SequenceClassificationModel};
+use rust_bert::pipelines::common::ModelType;
+use rust_bert::resources::{LocalResource, ModelResource};
+use std::path::Path;
+use std::sync::Mutex;
+use vader_sentiment::SentimentIntensityAnalyzer;
+
+use crate::SentimentError;
+
+/// Raw sentiment scores produced by a backend, independent of classification
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SentimentScores {
+    pub compound: f64,
+    pub positive: f64,
+    pub negative: f64,
+    pub neutral: f64,
+}
+
+/// A pluggable sentiment scoring engine
+///
+/// Implementations turn raw text into [`SentimentScores`]; everything
+/// downstream (classification, output formatting) works the same
+/// regardless of which backend produced the numbers. `Send + Sync` so a
+/// single backend instance can be shared across the async worker pool used
+/// for file analysis.
+pub trait SentimentBackend: Send + Sync {
+    fn scores(&self, text: &str) -> SentimentScores;
+}
+
+/// Lexicon/rule-based backend using VADER (fast, no model files required)
+pub struct VaderBackend {
+    analyzer: SentimentIntensityAnalyzer<'static>,
+}
+
+impl VaderBackend {
+    pub fn new() -> Self {
+        Self {
+            analyzer: SentimentIntensityAnalyzer::new(),
+        }
+    }
+}
+
+impl SentimentBackend for VaderBackend {
+    fn scores(&self, text: &str) -> SentimentScores {
+        let scores = self.analyzer.polarity_scores(text);
+
+        SentimentScores {
+            compound: *scores.get("compound").unwrap_or(&0.0),
+            positive: *scores.get("pos").unwrap_or(&0.0),
+            negative: *scores.get("neg").unwrap_or(&0.0),
+            neutral: *scores.get("neu").unwrap_or(&0.0),
+        }
+    }
+}
+
+/// Neural backend using a DistilBERT sequence-classification model loaded
+/// from a local directory (`config.json`, `vocab.txt` and `rust_model.ot`),
+/// mirroring the rust-bert NER/sentiment pipelines. Only DistilBert's
+/// WordPiece vocab is supported; a RoBERTa export (byte-level BPE,
+/// `vocab.json`+`merges.txt`) will fail to load.
+pub struct TransformerBackend {
+    // rust-bert's model isn't safely callable from multiple threads at once;
+    // a mutex lets it be shared (as `Send + Sync`) across the worker pool.
+    model: Mutex<SequenceClassificationModel>,
+}
+
+impl TransformerBackend {
+    pub fn new(model_dir: &str) -> Result<Self, SentimentError> {
+        let dir = Path::new(model_dir);
+        let config_resource = LocalResource::from(dir.join("config.json"));
+        let vocab_resource = LocalResource::from(dir.join("vocab.txt"));
+        let weights_resource = LocalResource::from(dir.join("rust_model.ot"));
+
+        let config = SequenceClassificationConfig::new(
+            ModelType::DistilBert,
+            ModelResource::Torch(Box::new(weights_resource)),
+            config_resource.into(),
+            vocab_resource.into(),
+            None,
+            true,
+            None,
+            None,
+        );
+
+        let model = SequenceClassificationModel::new(config).map_err(|e| {
+            SentimentError::ConfigError(format!(
+                "Failed to load transformer model from '{}': {}",
+                model_dir, e
+            ))
+        })?;
+
+        Ok(Self { model: Mutex::new(model) })
+    }
+}
+
+impl SentimentBackend for TransformerBackend {
+    fn scores(&self, text: &str) -> SentimentScores {
+        // The classifier returns one label per input with a confidence score;
+        // map it onto the same compound/positive/negative/neutral shape VADER
+        // produces so downstream code doesn't need to know which backend ran.
+        let model = self.model.lock().unwrap();
+        let predictions = model.predict(&[text]);
+        let label = predictions
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| panic!("transformer backend returned no prediction for input"));
+
+        let signed = match label.text.to_lowercase().as_str() {
+            "positive" | "label_1" => label.score,
+            "negative" | "label_0" => -label.score,
+            _ => 0.0,
+        };
+
+        SentimentScores {
+            compound: signed,
+            positive: signed.max(0.0),
+            negative: (-signed).max(0.0),
+            neutral: 1.0 - label.score,
+        }
+    }
+}
+
+/// Construct the configured backend, loading model files once per run
+pub fn build_backend(backend: &str, model_dir: &str) -> Result<Box<dyn SentimentBackend>, SentimentError> {
+    match backend {
+        "vader" => Ok(Box::new(VaderBackend::new())),
+        "transformer" => Ok(Box::new(TransformerBackend::new(model_dir)?)),
+        other => Err(SentimentError::ConfigError(format!(
+            "Unknown analysis backend '{}', expected 'vader' or 'transformer'",
+            other
+        ))),
+    }
+}
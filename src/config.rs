@@ -0,0 +1,481 @@
+//! Layered configuration loading.
+//!
+//! Settings are merged, in increasing priority, from: built-in defaults, a
+//! config file auto-detected by extension (`config.yaml`, `config.toml` or
+//! `config.json`), `SENTIMENTAL_*` environment variables, and explicit CLI
+//! flags. Each layer only overrides the fields it actually sets, and the
+//! origin of every field is tracked so `--print-config` can show where each
+//! value came from.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::SentimentError;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Config {
+    pub analysis: AnalysisConfig,
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub processing: ProcessingConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisConfig {
+    /// Which `SentimentBackend` to use: "vader" (default, lexicon-based) or
+    /// "transformer" (neural, loaded from `model_dir`)
+    #[serde(default = "default_backend")]
+    pub backend: String,
+    /// Directory containing the transformer model's config/vocab/weights
+    /// files; only read when `backend` is "transformer"
+    #[serde(default)]
+    pub model_dir: String,
+    pub positive_threshold: f64,
+    pub negative_threshold: f64,
+    pub include_compound: bool,
+    pub include_individual: bool,
+    /// Also compute per-entity aspect sentiment on `analyze`/`analyze-file`
+    /// (the `aspects` subcommand always computes it regardless of this flag)
+    #[serde(default)]
+    pub aspects: bool,
+}
+
+pub fn default_backend() -> String {
+    "vader".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    pub level: String,
+    pub file: String,
+    /// Roll trigger for the `file` target: "size" (every `SIZE_TRIGGER_BYTES`)
+    /// or "daily" (once per calendar day)
+    #[serde(default = "default_rotation")]
+    pub rotation: String,
+    /// Number of compressed archives to keep before the oldest is discarded
+    #[serde(default = "default_max_files")]
+    pub max_files: u32,
+    /// log4rs pattern encoder string, e.g. "{d} {h({l})} [{M}] {m}{n}"
+    #[serde(default = "default_pattern")]
+    pub pattern: String,
+    /// Where log output goes: "console" (default), "file" or "syslog"
+    #[serde(default = "default_log_target")]
+    pub target: String,
+}
+
+pub fn default_rotation() -> String {
+    "size".to_string()
+}
+
+pub fn default_max_files() -> u32 {
+    5
+}
+
+pub fn default_pattern() -> String {
+    "{d(%Y-%m-%d %H:%M:%S)} {h({l})} [{M}] {m}{n}".to_string()
+}
+
+pub fn default_log_target() -> String {
+    "console".to_string()
+}
+
+/// Output formatting configuration
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// One of "text" (default), "json", "ndjson" or "csv"
+    #[serde(default = "default_output_format")]
+    pub format: String,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            format: default_output_format(),
+        }
+    }
+}
+
+fn default_output_format() -> String {
+    "text".to_string()
+}
+
+/// Async streaming/parallel analysis tuning for `analyze-file`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessingConfig {
+    /// Number of chunks analyzed concurrently
+    #[serde(default = "default_workers")]
+    pub workers: usize,
+    /// Lines grouped into a single worker task
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+    /// Log an `info` progress line every this many processed lines
+    #[serde(default = "default_progress_interval")]
+    pub progress_interval: usize,
+}
+
+impl Default for ProcessingConfig {
+    fn default() -> Self {
+        Self {
+            workers: default_workers(),
+            chunk_size: default_chunk_size(),
+            progress_interval: default_progress_interval(),
+        }
+    }
+}
+
+pub fn default_workers() -> usize {
+    4
+}
+
+pub fn default_chunk_size() -> usize {
+    100
+}
+
+pub fn default_progress_interval() -> usize {
+    1000
+}
+
+/// The built-in defaults, used as the bottom layer of the merge.
+pub fn default_config() -> Config {
+    Config {
+        analysis: AnalysisConfig {
+            backend: default_backend(),
+            model_dir: "".to_string(),
+            positive_threshold: 0.05,
+            negative_threshold: -0.05,
+            include_compound: true,
+            include_individual: false,
+            aspects: false,
+        },
+        logging: LoggingConfig {
+            level: "info".to_string(),
+            file: "".to_string(),
+            rotation: default_rotation(),
+            max_files: default_max_files(),
+            pattern: default_pattern(),
+            target: default_log_target(),
+        },
+        output: OutputConfig::default(),
+        processing: ProcessingConfig::default(),
+    }
+}
+
+/// Where a config value ultimately came from, for `--print-config`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    Default,
+    File(String),
+    Env,
+    Cli,
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigOrigin::Default => write!(f, "default"),
+            ConfigOrigin::File(path) => write!(f, "file ({})", path),
+            ConfigOrigin::Env => write!(f, "environment"),
+            ConfigOrigin::Cli => write!(f, "cli"),
+        }
+    }
+}
+
+/// Per-field origins, keyed by dotted field path (e.g. "analysis.backend").
+pub type ConfigOrigins = HashMap<String, ConfigOrigin>;
+
+fn default_origins() -> ConfigOrigins {
+    [
+        "analysis.backend",
+        "analysis.model_dir",
+        "analysis.positive_threshold",
+        "analysis.negative_threshold",
+        "analysis.include_compound",
+        "analysis.include_individual",
+        "analysis.aspects",
+        "logging.level",
+        "logging.file",
+        "logging.rotation",
+        "logging.max_files",
+        "logging.pattern",
+        "logging.target",
+        "output.format",
+        "processing.workers",
+        "processing.chunk_size",
+        "processing.progress_interval",
+    ]
+    .iter()
+    .map(|field| (field.to_string(), ConfigOrigin::Default))
+    .collect()
+}
+
+/// Partial, all-optional mirror of [`Config`] used for file layers, where a
+/// user may only want to set a handful of fields.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PartialConfig {
+    analysis: PartialAnalysisConfig,
+    logging: PartialLoggingConfig,
+    output: PartialOutputConfig,
+    processing: PartialProcessingConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PartialAnalysisConfig {
+    backend: Option<String>,
+    model_dir: Option<String>,
+    positive_threshold: Option<f64>,
+    negative_threshold: Option<f64>,
+    include_compound: Option<bool>,
+    include_individual: Option<bool>,
+    aspects: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PartialLoggingConfig {
+    level: Option<String>,
+    file: Option<String>,
+    rotation: Option<String>,
+    max_files: Option<u32>,
+    pattern: Option<String>,
+    target: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PartialOutputConfig {
+    format: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PartialProcessingConfig {
+    workers: Option<usize>,
+    chunk_size: Option<usize>,
+    progress_interval: Option<usize>,
+}
+
+/// Explicit CLI flag overrides; `None` means "not passed on this invocation".
+#[derive(Debug, Default)]
+pub struct CliOverrides {
+    pub positive_threshold: Option<f64>,
+    pub negative_threshold: Option<f64>,
+    pub log_level: Option<String>,
+    pub output: Option<String>,
+}
+
+/// The config file names tried, in order, when no explicit path is given.
+const AUTO_DETECT_CANDIDATES: &[&str] = &["config.yaml", "config.toml", "config.json"];
+
+fn parse_partial_config(path: &str, content: &str) -> Result<PartialConfig, SentimentError> {
+    match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("yaml") | Some("yml") => Ok(serde_yaml::from_str(content)?),
+        Some("toml") => toml::from_str(content)
+            .map_err(|e| SentimentError::ConfigError(format!("TOML parsing error in '{}': {}", path, e))),
+        Some("json") => Ok(serde_json::from_str(content)?),
+        Some(other) => Err(SentimentError::ConfigError(format!(
+            "Unsupported config file extension '.{}' for '{}', expected .yaml, .toml or .json",
+            other, path
+        ))),
+        None => Err(SentimentError::ConfigError(format!(
+            "Config file '{}' has no extension to detect its format from",
+            path
+        ))),
+    }
+}
+
+fn merge_file_layer(config: &mut Config, origins: &mut ConfigOrigins, path: &str, partial: PartialConfig) {
+    macro_rules! apply {
+        ($field:expr, $key:literal, $value:expr) => {
+            if let Some(value) = $value {
+                $field = value;
+                origins.insert($key.to_string(), ConfigOrigin::File(path.to_string()));
+            }
+        };
+    }
+
+    apply!(config.analysis.backend, "analysis.backend", partial.analysis.backend);
+    apply!(config.analysis.model_dir, "analysis.model_dir", partial.analysis.model_dir);
+    apply!(
+        config.analysis.positive_threshold,
+        "analysis.positive_threshold",
+        partial.analysis.positive_threshold
+    );
+    apply!(
+        config.analysis.negative_threshold,
+        "analysis.negative_threshold",
+        partial.analysis.negative_threshold
+    );
+    apply!(
+        config.analysis.include_compound,
+        "analysis.include_compound",
+        partial.analysis.include_compound
+    );
+    apply!(
+        config.analysis.include_individual,
+        "analysis.include_individual",
+        partial.analysis.include_individual
+    );
+    apply!(config.analysis.aspects, "analysis.aspects", partial.analysis.aspects);
+    apply!(config.logging.level, "logging.level", partial.logging.level);
+    apply!(config.logging.file, "logging.file", partial.logging.file);
+    apply!(config.logging.rotation, "logging.rotation", partial.logging.rotation);
+    apply!(config.logging.max_files, "logging.max_files", partial.logging.max_files);
+    apply!(config.logging.pattern, "logging.pattern", partial.logging.pattern);
+    apply!(config.logging.target, "logging.target", partial.logging.target);
+    apply!(config.output.format, "output.format", partial.output.format);
+    apply!(config.processing.workers, "processing.workers", partial.processing.workers);
+    apply!(config.processing.chunk_size, "processing.chunk_size", partial.processing.chunk_size);
+    apply!(
+        config.processing.progress_interval,
+        "processing.progress_interval",
+        partial.processing.progress_interval
+    );
+}
+
+fn apply_env_layer(config: &mut Config, origins: &mut ConfigOrigins) {
+    macro_rules! apply_parsed {
+        ($field:expr, $key:literal, $env:literal) => {
+            if let Ok(value) = std::env::var($env) {
+                match value.parse() {
+                    Ok(parsed) => {
+                        $field = parsed;
+                        origins.insert($key.to_string(), ConfigOrigin::Env);
+                    }
+                    Err(_) => {
+                        log::warn!("Ignoring {} = '{}': not a valid value", $env, value);
+                    }
+                }
+            }
+        };
+    }
+
+    apply_parsed!(config.analysis.backend, "analysis.backend", "SENTIMENTAL_BACKEND");
+    apply_parsed!(config.analysis.model_dir, "analysis.model_dir", "SENTIMENTAL_MODEL_DIR");
+    apply_parsed!(
+        config.analysis.positive_threshold,
+        "analysis.positive_threshold",
+        "SENTIMENTAL_POSITIVE_THRESHOLD"
+    );
+    apply_parsed!(
+        config.analysis.negative_threshold,
+        "analysis.negative_threshold",
+        "SENTIMENTAL_NEGATIVE_THRESHOLD"
+    );
+    apply_parsed!(
+        config.analysis.include_compound,
+        "analysis.include_compound",
+        "SENTIMENTAL_INCLUDE_COMPOUND"
+    );
+    apply_parsed!(
+        config.analysis.include_individual,
+        "analysis.include_individual",
+        "SENTIMENTAL_INCLUDE_INDIVIDUAL"
+    );
+    apply_parsed!(config.analysis.aspects, "analysis.aspects", "SENTIMENTAL_ASPECTS");
+    apply_parsed!(config.logging.level, "logging.level", "SENTIMENTAL_LOG_LEVEL");
+    apply_parsed!(config.logging.file, "logging.file", "SENTIMENTAL_LOG_FILE");
+    apply_parsed!(config.logging.rotation, "logging.rotation", "SENTIMENTAL_LOG_ROTATION");
+    apply_parsed!(config.logging.max_files, "logging.max_files", "SENTIMENTAL_LOG_MAX_FILES");
+    apply_parsed!(config.logging.pattern, "logging.pattern", "SENTIMENTAL_LOG_PATTERN");
+    apply_parsed!(config.logging.target, "logging.target", "SENTIMENTAL_LOG_TARGET");
+    apply_parsed!(config.output.format, "output.format", "SENTIMENTAL_OUTPUT_FORMAT");
+    apply_parsed!(config.processing.workers, "processing.workers", "SENTIMENTAL_WORKERS");
+    apply_parsed!(
+        config.processing.chunk_size,
+        "processing.chunk_size",
+        "SENTIMENTAL_CHUNK_SIZE"
+    );
+    apply_parsed!(
+        config.processing.progress_interval,
+        "processing.progress_interval",
+        "SENTIMENTAL_PROGRESS_INTERVAL"
+    );
+}
+
+fn apply_cli_layer(config: &mut Config, origins: &mut ConfigOrigins, overrides: CliOverrides) {
+    if let Some(value) = overrides.positive_threshold {
+        config.analysis.positive_threshold = value;
+        origins.insert("analysis.positive_threshold".to_string(), ConfigOrigin::Cli);
+    }
+    if let Some(value) = overrides.negative_threshold {
+        config.analysis.negative_threshold = value;
+        origins.insert("analysis.negative_threshold".to_string(), ConfigOrigin::Cli);
+    }
+    if let Some(value) = overrides.log_level {
+        config.logging.level = value;
+        origins.insert("logging.level".to_string(), ConfigOrigin::Cli);
+    }
+    if let Some(value) = overrides.output {
+        config.output.format = value;
+        origins.insert("output.format".to_string(), ConfigOrigin::Cli);
+    }
+}
+
+/// Load the layered configuration, merging defaults, an auto-detected or
+/// explicit config file, `SENTIMENTAL_*` environment variables, and CLI
+/// overrides, in that priority order.
+///
+/// `config_path` is used as-is if given; otherwise the first of
+/// [`AUTO_DETECT_CANDIDATES`] that exists on disk is used. It's not an error
+/// for no config file to exist — the defaults (plus env/CLI layers) apply.
+pub fn load_layered_config(config_path: Option<&str>, cli_overrides: CliOverrides) -> Result<(Config, ConfigOrigins), SentimentError> {
+    let mut config = default_config();
+    let mut origins = default_origins();
+
+    let resolved_path = config_path
+        .map(|p| p.to_string())
+        .or_else(|| AUTO_DETECT_CANDIDATES.iter().find(|p| Path::new(p).exists()).map(|p| p.to_string()));
+
+    if let Some(path) = resolved_path {
+        if Path::new(&path).exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let partial = parse_partial_config(&path, &content)?;
+            merge_file_layer(&mut config, &mut origins, &path, partial);
+        } else if config_path.is_some() {
+            return Err(SentimentError::ConfigError(format!("Config file '{}' not found", path)));
+        }
+    }
+
+    apply_env_layer(&mut config, &mut origins);
+    apply_cli_layer(&mut config, &mut origins, cli_overrides);
+
+    Ok((config, origins))
+}
+
+/// Render the resolved config and the origin of each field, for
+/// `--print-config`.
+pub fn format_config_origins(config: &Config, origins: &ConfigOrigins) -> String {
+    let mut out = String::new();
+    let rows = [
+        ("analysis.backend", config.analysis.backend.clone()),
+        ("analysis.model_dir", config.analysis.model_dir.clone()),
+        ("analysis.positive_threshold", config.analysis.positive_threshold.to_string()),
+        ("analysis.negative_threshold", config.analysis.negative_threshold.to_string()),
+        ("analysis.include_compound", config.analysis.include_compound.to_string()),
+        ("analysis.include_individual", config.analysis.include_individual.to_string()),
+        ("analysis.aspects", config.analysis.aspects.to_string()),
+        ("logging.level", config.logging.level.clone()),
+        ("logging.file", config.logging.file.clone()),
+        ("logging.rotation", config.logging.rotation.clone()),
+        ("logging.max_files", config.logging.max_files.to_string()),
+        ("logging.pattern", config.logging.pattern.clone()),
+        ("logging.target", config.logging.target.clone()),
+        ("output.format", config.output.format.clone()),
+        ("processing.workers", config.processing.workers.to_string()),
+        ("processing.chunk_size", config.processing.chunk_size.to_string()),
+        ("processing.progress_interval", config.processing.progress_interval.to_string()),
+    ];
+
+    for (key, value) in rows {
+        let origin = origins.get(key).cloned().unwrap_or(ConfigOrigin::Default);
+        out.push_str(&format!("{} = {} ({})\n", key, value, origin));
+    }
+
+    out
+}
@@ -0,0 +1,268 @@
+//! Core sentiment analysis types and logic, reusable outside of the CLI binary.
+
+pub mod backend;
+pub mod config;
+pub mod logging;
+pub mod ner;
+pub mod output;
+
+use serde::Serialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use backend::SentimentBackend;
+pub use config::{default_backend, AnalysisConfig, CliOverrides, Config, ConfigOrigin, ConfigOrigins, LoggingConfig, OutputConfig};
+use ner::{AspectSentiment, EntityExtractor};
+
+/// Sentiment analysis result
+#[derive(Debug, Serialize)]
+pub struct SentimentResult {
+    pub text: String,
+    pub classification: String,
+    pub scores: SentimentScores,
+    /// Per-entity sentiment, populated by the `aspects` subcommand, and by
+    /// `analyze`/`analyze-file` when `analysis.aspects` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspects: Option<HashMap<String, AspectSentiment>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SentimentScores {
+    pub compound: Option<f64>,
+    pub positive: Option<f64>,
+    pub negative: Option<f64>,
+    pub neutral: Option<f64>,
+}
+
+/// Custom error types for the application
+#[derive(Error, Debug)]
+pub enum SentimentError {
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("YAML parsing error: {0}")]
+    YamlError(#[from] serde_yaml::Error),
+
+    #[error("Logging initialization error: {0}")]
+    LoggingError(#[from] log::SetLoggerError),
+
+    #[error("JSON output error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("CSV output error: {0}")]
+    CsvError(#[from] csv::Error),
+}
+
+/// Classify sentiment based on thresholds
+pub fn classify_sentiment(score: f64, config: &AnalysisConfig) -> String {
+    if score >= config.positive_threshold {
+        "Positive".to_string()
+    } else if score <= config.negative_threshold {
+        "Negative".to_string()
+    } else {
+        "Neutral".to_string()
+    }
+}
+
+/// Analyze sentiment of a single text using the given backend
+pub fn analyze_text(text: &str, backend: &dyn SentimentBackend, config: &AnalysisConfig) -> SentimentResult {
+    let scores = backend.scores(text);
+    let classification = classify_sentiment(scores.compound, config);
+
+    SentimentResult {
+        text: text.to_string(),
+        classification,
+        scores: SentimentScores {
+            compound: if config.include_compound {
+                Some(scores.compound)
+            } else {
+                None
+            },
+            positive: if config.include_individual {
+                Some(scores.positive)
+            } else {
+                None
+            },
+            negative: if config.include_individual {
+                Some(scores.negative)
+            } else {
+                None
+            },
+            neutral: if config.include_individual {
+                Some(scores.neutral)
+            } else {
+                None
+            },
+        },
+        aspects: None,
+    }
+}
+
+/// Analyze sentiment of a single text, plus per-entity sentiment for each
+/// aspect/entity an NER pass finds in it
+pub fn analyze_text_with_aspects(
+    text: &str,
+    backend: &dyn SentimentBackend,
+    extractor: &EntityExtractor,
+    config: &AnalysisConfig,
+) -> SentimentResult {
+    let mut result = analyze_text(text, backend, config);
+    result.aspects = Some(ner::analyze_aspects(text, extractor, backend, config));
+    result
+}
+
+/// Analyze a batch of texts with a shared backend, preserving input order.
+///
+/// This is the reusable entry point for library consumers that want
+/// structured [`SentimentResult`]s without going through the CLI's
+/// printing code.
+pub fn analyze_many<I, S>(texts: I, backend: &dyn SentimentBackend, config: &AnalysisConfig) -> Vec<SentimentResult>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    texts
+        .into_iter()
+        .map(|text| analyze_text(text.as_ref(), backend, config))
+        .collect()
+}
+
+/// Analyze a batch of texts with a shared backend, plus per-entity aspect
+/// sentiment from a shared [`EntityExtractor`], preserving input order.
+pub fn analyze_many_with_aspects<I, S>(
+    texts: I,
+    backend: &dyn SentimentBackend,
+    extractor: &EntityExtractor,
+    config: &AnalysisConfig,
+) -> Vec<SentimentResult>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    texts
+        .into_iter()
+        .map(|text| analyze_text_with_aspects(text.as_ref(), backend, extractor, config))
+        .collect()
+}
+
+/// Stream a (potentially multi-gigabyte) file of one-text-per-line input
+/// through a bounded pool of worker tasks, without reading the whole file into
+/// memory and using all configured workers, while preserving the original
+/// line order.
+///
+/// Lines are grouped into chunks of `processing.chunk_size` so a single
+/// worker task amortizes its scheduling cost over several lines; at most
+/// `processing.workers` chunks are analyzed concurrently. Progress is logged
+/// at `info` every `processing.progress_interval` completed lines.
+///
+/// Results are handed to `on_chunk` in input order instead of being collected
+/// for the whole file: completed chunks are reaped opportunistically while
+/// later lines are still being read, and each is handed over once every
+/// earlier chunk has been. Chunks that finish ahead of an earlier, slower one
+/// are held until it completes; the number held this way is not capped by
+/// `processing.workers`.
+///
+/// When `extractor` is `Some`, each chunk also gets per-entity aspect
+/// sentiment (mirrors `analysis.aspects` in [`AnalysisConfig`]).
+pub async fn analyze_file_streaming(
+    file_path: &str,
+    backend: std::sync::Arc<dyn SentimentBackend>,
+    extractor: Option<std::sync::Arc<EntityExtractor>>,
+    analysis: &AnalysisConfig,
+    processing: &config::ProcessingConfig,
+    mut on_chunk: impl FnMut(Vec<SentimentResult>) -> Result<(), SentimentError>,
+) -> Result<(), SentimentError> {
+    use tokio::io::AsyncBufReadExt;
+
+    let file = tokio::fs::File::open(file_path).await?;
+    let mut lines = tokio::io::BufReader::new(file).lines();
+
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(processing.workers.max(1)));
+    let processed = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut tasks = tokio::task::JoinSet::new();
+
+    // Chunks can finish out of order; `pending` holds ones that finished
+    // ahead of `next_start` until it's their turn to be handed to `on_chunk`.
+    let mut pending: std::collections::BTreeMap<usize, Vec<SentimentResult>> = std::collections::BTreeMap::new();
+    let mut next_start = 0usize;
+
+    let mut chunk: Vec<String> = Vec::with_capacity(processing.chunk_size);
+    let mut chunk_start = 0usize;
+    let mut total_lines = 0usize;
+
+    macro_rules! spawn_chunk {
+        ($chunk:expr, $start:expr) => {{
+            let lines = $chunk;
+            if !lines.is_empty() {
+                let permit = semaphore.clone().acquire_owned().await.expect("semaphore not closed");
+                let backend = backend.clone();
+                let extractor = extractor.clone();
+                let analysis = analysis.clone();
+                let processed = processed.clone();
+                let progress_interval = processing.progress_interval;
+                let start = $start;
+
+                tasks.spawn(async move {
+                    let _permit = permit;
+                    let results = match &extractor {
+                        Some(extractor) => analyze_many_with_aspects(&lines, backend.as_ref(), extractor, &analysis),
+                        None => analyze_many(&lines, backend.as_ref(), &analysis),
+                    };
+
+                    let count = processed.fetch_add(lines.len(), std::sync::atomic::Ordering::Relaxed) + lines.len();
+                    if progress_interval > 0 && count / progress_interval > (count - lines.len()) / progress_interval {
+                        log::info!("Processed {} lines", count);
+                    }
+
+                    (start, results)
+                });
+            }
+        }};
+    }
+
+    // Drain whatever chunks have already finished (without blocking) and
+    // hand any now-contiguous run of them to `on_chunk`, in order.
+    macro_rules! reap_ready {
+        () => {{
+            while let Some(outcome) = tasks.try_join_next() {
+                let (start, results) = outcome.map_err(|e| SentimentError::ConfigError(format!("worker task failed: {}", e)))?;
+                pending.insert(start, results);
+            }
+            while let Some(results) = pending.remove(&next_start) {
+                next_start += results.len();
+                on_chunk(results)?;
+            }
+        }};
+    }
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if chunk.is_empty() {
+            chunk_start = total_lines;
+        }
+        chunk.push(line);
+        total_lines += 1;
+
+        if chunk.len() >= processing.chunk_size {
+            spawn_chunk!(std::mem::take(&mut chunk), chunk_start);
+            reap_ready!();
+        }
+    }
+    spawn_chunk!(std::mem::take(&mut chunk), chunk_start);
+
+    while let Some(outcome) = tasks.join_next().await {
+        let (start, results) = outcome.map_err(|e| SentimentError::ConfigError(format!("worker task failed: {}", e)))?;
+        pending.insert(start, results);
+        while let Some(results) = pending.remove(&next_start) {
+            next_start += results.len();
+            on_chunk(results)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,62 +1,12 @@
 use clap::{Parser, Subcommand};
 use log::{error, info, warn};
-use serde::{Deserialize, Serialize};
-use std::{fs};
-use thiserror::Error;
-use vader_sentiment::SentimentIntensityAnalyzer;
-
-/// Configuration for the sentiment analyzer
-#[derive(Debug, Serialize, Deserialize)]
-struct Config {
-    analysis: AnalysisConfig,
-    logging: LoggingConfig,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct AnalysisConfig {
-    positive_threshold: f64,
-    negative_threshold: f64,
-    include_compound: bool,
-    include_individual: bool,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct LoggingConfig {
-    level: String,
-    file: String,
-}
-
-/// Sentiment analysis result
-#[derive(Debug, Serialize)]
-struct SentimentResult {
-    text: String,
-    classification: String,
-    scores: SentimentScores,
-}
-
-#[derive(Debug, Serialize)]
-struct SentimentScores {
-    compound: Option<f64>,
-    positive: Option<f64>,
-    negative: Option<f64>,
-    neutral: Option<f64>,
-}
-
-/// Custom error types for the application
-#[derive(Error, Debug)]
-enum SentimentError {
-    #[error("Configuration error: {0}")]
-    ConfigError(String),
-
-    #[error("IO error: {0}")]
-    IoError(#[from] std::io::Error),
-
-    #[error("YAML parsing error: {0}")]
-    YamlError(#[from] serde_yaml::Error),
-
-    #[error("Logging initialization error: {0}")]
-    LoggingError(#[from] log::SetLoggerError),
-}
+use sentimental::backend::{build_backend, SentimentBackend};
+use sentimental::config::{default_config, format_config_origins, load_layered_config};
+use sentimental::logging::init_logging;
+use sentimental::ner::EntityExtractor;
+use sentimental::output::{write_results, OutputFormat, ResultWriter};
+use sentimental::{analyze_file_streaming, analyze_many, analyze_many_with_aspects, analyze_text_with_aspects, CliOverrides, Config, SentimentError};
+use std::sync::Arc;
 
 /// CLI interface definition
 #[derive(Parser, Debug)]
@@ -66,6 +16,44 @@ enum SentimentError {
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Print the resolved configuration and the origin of each value, then exit
+    #[arg(long, global = true)]
+    print_config: bool,
+}
+
+#[derive(Parser, Debug)]
+struct ConfigArgs {
+    /// Config file path (auto-detected from config.yaml/.toml/.json if omitted)
+    #[arg(short, long)]
+    config: Option<String>,
+
+    /// Output format: text, json, ndjson or csv (overrides config)
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Override the positive-sentiment threshold
+    #[arg(long)]
+    positive_threshold: Option<f64>,
+
+    /// Override the negative-sentiment threshold
+    #[arg(long)]
+    negative_threshold: Option<f64>,
+
+    /// Override the log level (debug/info/warn/error)
+    #[arg(long)]
+    log_level: Option<String>,
+}
+
+impl ConfigArgs {
+    fn overrides(&self) -> CliOverrides {
+        CliOverrides {
+            positive_threshold: self.positive_threshold,
+            negative_threshold: self.negative_threshold,
+            log_level: self.log_level.clone(),
+            output: self.output.clone(),
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -74,133 +62,73 @@ enum Commands {
     Analyze {
         /// Text to analyze
         text: String,
-        
-        /// Optional config file path
-        #[arg(short, long, default_value = "config.yaml")]
-        config: String,
+
+        #[command(flatten)]
+        config_args: ConfigArgs,
     },
     /// Analyze sentiment of a text file (one sentence per line)
     AnalyzeFile {
         /// Path to text file
         file: String,
-        
-        /// Optional config file path
-        #[arg(short, long, default_value = "config.yaml")]
-        config: String,
+
+        #[command(flatten)]
+        config_args: ConfigArgs,
+    },
+    /// Analyze aspect/entity-level sentiment: run NER over the text, then
+    /// score each entity from the sentence windows it appears in
+    Aspects {
+        /// Text to analyze
+        text: String,
+
+        #[command(flatten)]
+        config_args: ConfigArgs,
     },
 }
 
-/// Load configuration from YAML file
-fn load_config(config_path: &str) -> Result<Config, SentimentError> {
-    let config_content = fs::read_to_string(config_path)?;
-    let config: Config = serde_yaml::from_str(&config_content)?;
-    Ok(config)
+fn resolve_config(config_args: &ConfigArgs) -> Config {
+    match load_layered_config(config_args.config.as_deref(), config_args.overrides()) {
+        Ok((config, _origins)) => config,
+        Err(e) => {
+            warn!("Failed to load config: {}. Using defaults.", e);
+            load_layered_config(None, config_args.overrides())
+                .map(|(config, _)| config)
+                .unwrap_or_else(|_| default_config())
+        }
+    }
 }
 
-/// Initialize logging system
-fn init_logging(config: &LoggingConfig) -> Result<(), SentimentError> {
-    let log_level = match config.level.to_lowercase().as_str() {
-        "debug" => log::LevelFilter::Debug,
-        "info" => log::LevelFilter::Info,
-        "warn" => log::LevelFilter::Warn,
-        "error" => log::LevelFilter::Error,
-        _ => log::LevelFilter::Info,
-    };
+/// Process a single text analysis with error handling
+fn process_text(text: &str, config_args: &ConfigArgs) -> Result<(), SentimentError> {
+    let config = resolve_config(config_args);
 
-    if config.file.is_empty() {
-        env_logger::Builder::new()
-            .filter_level(log_level)
-            .try_init()?;
-    } else {
-        let log_file = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&config.file)?;
-            
+    // Initialize logging (fallback to console if that fails)
+    if let Err(e) = init_logging(&config.logging) {
+        eprintln!("Failed to initialize logging: {}. Using console only.", e);
         env_logger::Builder::new()
-            .filter_level(log_level)
-            .target(env_logger::Target::Pipe(Box::new(log_file)))
-            .try_init()?;
+            .filter_level(log::LevelFilter::Info)
+            .try_init()
+            .map_err(SentimentError::LoggingError)?;
     }
 
-    Ok(())
-}
+    let backend = build_backend(&config.analysis.backend, &config.analysis.model_dir)?;
+    let format = OutputFormat::parse(&config.output.format)?;
 
-/// Classify sentiment based on thresholds
-fn classify_sentiment(score: f64, config: &AnalysisConfig) -> String {
-    if score >= config.positive_threshold {
-        "Positive".to_string()
-    } else if score <= config.negative_threshold {
-        "Negative".to_string()
+    info!("Analyzing text: '{}'", text);
+    let results = if config.analysis.aspects {
+        let extractor = EntityExtractor::new()?;
+        analyze_many_with_aspects([text], backend.as_ref(), &extractor, &config.analysis)
     } else {
-        "Neutral".to_string()
-    }
-}
+        analyze_many([text], backend.as_ref(), &config.analysis)
+    };
 
-/// Analyze sentiment of a single text
-fn analyze_text(text: &str, config: &AnalysisConfig) -> SentimentResult {
-    let analyzer = SentimentIntensityAnalyzer::new();
-    let scores = analyzer.polarity_scores(text);
-
-    // Extract scores from the HashMap
-    let compound = *scores.get("compound").unwrap_or(&0.0);
-    let positive = *scores.get("pos").unwrap_or(&0.0);
-    let negative = *scores.get("neg").unwrap_or(&0.0);
-    let neutral = *scores.get("neu").unwrap_or(&0.0);
-
-    let classification = classify_sentiment(compound, config);
-
-    SentimentResult {
-        text: text.to_string(),
-        classification,
-        scores: SentimentScores {
-            compound: if config.include_compound {
-                Some(compound)
-            } else {
-                None
-            },
-            positive: if config.include_individual {
-                Some(positive)
-            } else {
-                None
-            },
-            negative: if config.include_individual {
-                Some(negative)
-            } else {
-                None
-            },
-            neutral: if config.include_individual {
-                Some(neutral)
-            } else {
-                None
-            },
-        },
-    }
+    write_results(&results, format, false)
 }
 
-/// Process a single text analysis with error handling
-fn process_text(text: &str, config_path: &str) -> Result<(), SentimentError> {
-    let config = match load_config(config_path) {
-        Ok(cfg) => cfg,
-        Err(e) => {
-            warn!("Failed to load config from '{}': {}. Using defaults.", config_path, e);
-            // Fallback to default config
-            Config {
-                analysis: AnalysisConfig {
-                    positive_threshold: 0.05,
-                    negative_threshold: -0.05,
-                    include_compound: true,
-                    include_individual: false,
-                },
-                logging: LoggingConfig {
-                    level: "info".to_string(),
-                    file: "".to_string(),
-                },
-            }
-        }
-    };
+/// Process a single aspect/entity-level sentiment analysis (the `aspects`
+/// subcommand always computes aspects, regardless of `analysis.aspects`)
+fn process_aspects(text: &str, config_args: &ConfigArgs) -> Result<(), SentimentError> {
+    let config = resolve_config(config_args);
 
-    // Initialize logging (fallback to console if config loading failed)
     if let Err(e) = init_logging(&config.logging) {
         eprintln!("Failed to initialize logging: {}. Using console only.", e);
         env_logger::Builder::new()
@@ -209,52 +137,20 @@ fn process_text(text: &str, config_path: &str) -> Result<(), SentimentError> {
             .map_err(SentimentError::LoggingError)?;
     }
 
-    info!("Analyzing text: '{}'", text);
-    let result = analyze_text(text, &config.analysis);
-    
-    println!("\nSentiment Analysis Result:");
-    println!("Text: {}", result.text);
-    println!("Classification: {}", result.classification);
-    
-    if let Some(compound) = result.scores.compound {
-        println!("Compound Score: {:.4}", compound);
-    }
-    
-    if config.analysis.include_individual {
-        if let Some(pos) = result.scores.positive {
-            println!("Positive: {:.4}", pos);
-        }
-        if let Some(neg) = result.scores.negative {
-            println!("Negative: {:.4}", neg);
-        }
-        if let Some(neu) = result.scores.neutral {
-            println!("Neutral: {:.4}", neu);
-        }
-    }
+    let backend = build_backend(&config.analysis.backend, &config.analysis.model_dir)?;
+    let extractor = EntityExtractor::new()?;
+    let format = OutputFormat::parse(&config.output.format)?;
 
-    Ok(())
+    info!("Analyzing aspects in text: '{}'", text);
+    let result = analyze_text_with_aspects(text, backend.as_ref(), &extractor, &config.analysis);
+
+    write_results(&[result], format, false)
 }
 
-/// Process a file with multiple texts (one per line)
-fn process_file(file_path: &str, config_path: &str) -> Result<(), SentimentError> {
-    let config = match load_config(config_path) {
-        Ok(cfg) => cfg,
-        Err(e) => {
-            warn!("Failed to load config from '{}': {}. Using defaults.", config_path, e);
-            Config {
-                analysis: AnalysisConfig {
-                    positive_threshold: 0.05,
-                    negative_threshold: -0.05,
-                    include_compound: true,
-                    include_individual: false,
-                },
-                logging: LoggingConfig {
-                    level: "info".to_string(),
-                    file: "".to_string(),
-                },
-            }
-        }
-    };
+/// Process a file with multiple texts (one per line), streaming it through a
+/// bounded pool of worker tasks so memory stays flat for large corpora.
+async fn process_file(file_path: &str, config_args: &ConfigArgs) -> Result<(), SentimentError> {
+    let config = resolve_config(config_args);
 
     if let Err(e) = init_logging(&config.logging) {
         eprintln!("Failed to initialize logging: {}. Using console only.", e);
@@ -264,53 +160,55 @@ fn process_file(file_path: &str, config_path: &str) -> Result<(), SentimentError
             .map_err(SentimentError::LoggingError)?;
     }
 
+    let backend: Arc<dyn SentimentBackend> = Arc::from(build_backend(&config.analysis.backend, &config.analysis.model_dir)?);
+    let extractor = if config.analysis.aspects {
+        Some(Arc::new(EntityExtractor::new()?))
+    } else {
+        None
+    };
+    let format = OutputFormat::parse(&config.output.format)?;
+
     info!("Processing file: {}", file_path);
-    let content = fs::read_to_string(file_path)?;
-    let lines = content.lines().filter(|l| !l.trim().is_empty());
-
-    println!("File Analysis Results:");
-    println!("=====================");
-
-    for (i, line) in lines.enumerate() {
-        match analyze_text(line, &config.analysis) {
-            result => {
-                println!("\nLine {}:", i + 1);
-                println!("Text: {}", result.text);
-                println!("Classification: {}", result.classification);
-                
-                if let Some(compound) = result.scores.compound {
-                    println!("Compound Score: {:.4}", compound);
-                }
-                
-                if config.analysis.include_individual {
-                    if let Some(pos) = result.scores.positive {
-                        println!("Positive: {:.4}", pos);
-                    }
-                    if let Some(neg) = result.scores.negative {
-                        println!("Negative: {:.4}", neg);
-                    }
-                    if let Some(neu) = result.scores.neutral {
-                        println!("Neutral: {:.4}", neu);
-                    }
-                }
-            }
-        }
-    }
+    let mut writer = ResultWriter::new(format, true)?;
+    analyze_file_streaming(file_path, backend, extractor, &config.analysis, &config.processing, |chunk| {
+        chunk.into_iter().try_for_each(|result| writer.write(result))
+    })
+    .await?;
 
-    Ok(())
+    writer.finish()
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
 
+    let config_args = match &cli.command {
+        Commands::Analyze { config_args, .. } => config_args,
+        Commands::AnalyzeFile { config_args, .. } => config_args,
+        Commands::Aspects { config_args, .. } => config_args,
+    };
+
+    if cli.print_config {
+        match load_layered_config(config_args.config.as_deref(), config_args.overrides()) {
+            Ok((config, origins)) => {
+                print!("{}", format_config_origins(&config, &origins));
+                return;
+            }
+            Err(e) => {
+                error!("Application error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     let result = match cli.command {
-        Commands::Analyze { text, config } => process_text(&text, &config),
-        Commands::AnalyzeFile { file, config } => process_file(&file, &config),
+        Commands::Analyze { text, config_args } => process_text(&text, &config_args),
+        Commands::AnalyzeFile { file, config_args } => process_file(&file, &config_args).await,
+        Commands::Aspects { text, config_args } => process_aspects(&text, &config_args),
     };
 
     if let Err(e) = result {
         error!("Application error: {}", e);
         std::process::exit(1);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,124 @@
+//! Aspect/entity-level sentiment: a named-entity-recognition pass over the
+//! input text, followed by per-entity sentiment scored from the sentence
+//! windows each entity appears in (rather than a single document-level
+//! compound score).
+
+use rust_bert::pipelines::ner::NERModel;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::backend::{SentimentBackend, SentimentScores as RawScores};
+use crate::{classify_sentiment, AnalysisConfig, SentimentError, SentimentScores};
+
+#[derive(Debug, Clone)]
+pub struct Entity {
+    pub text: String,
+    pub label: String,
+}
+
+/// Wraps the rust-bert NER pipeline, loading the model once for reuse
+/// across every text passed to `aspects`.
+pub struct EntityExtractor {
+    // Mirrors `TransformerBackend`: the model isn't safely callable from
+    // multiple threads at once, so a mutex guards shared access.
+    model: Mutex<NERModel>,
+}
+
+impl EntityExtractor {
+    pub fn new() -> Result<Self, SentimentError> {
+        let model = NERModel::new(Default::default())
+            .map_err(|e| SentimentError::ConfigError(format!("Failed to load NER model: {}", e)))?;
+
+        Ok(Self { model: Mutex::new(model) })
+    }
+
+    /// Extract the named entities the NER model tags in a single piece of
+    /// text, deduplicated by surface form. Words the model does not tag as
+    /// entities (such as plain noun phrases) are not returned.
+    fn extract(&self, text: &str) -> Vec<Entity> {
+        let model = self.model.lock().unwrap();
+        let predictions = model.predict(&[text]);
+
+        let mut seen = HashSet::new();
+        predictions
+            .into_iter()
+            .flatten()
+            .filter(|entity| seen.insert(entity.word.clone()))
+            .map(|entity| Entity {
+                text: entity.word,
+                label: entity.label,
+            })
+            .collect()
+    }
+}
+
+/// Per-entity sentiment: classification + scores, aggregated over the
+/// sentence windows the entity appears in.
+#[derive(Debug, Serialize)]
+pub struct AspectSentiment {
+    pub classification: String,
+    pub scores: SentimentScores,
+}
+
+/// Split text into naive sentence windows on `.`, `!` and `?`.
+fn sentence_windows(text: &str) -> Vec<&str> {
+    text.split_inclusive(['.', '!', '?'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn average(scores: &[RawScores]) -> RawScores {
+    let count = scores.len() as f64;
+    RawScores {
+        compound: scores.iter().map(|s| s.compound).sum::<f64>() / count,
+        positive: scores.iter().map(|s| s.positive).sum::<f64>() / count,
+        negative: scores.iter().map(|s| s.negative).sum::<f64>() / count,
+        neutral: scores.iter().map(|s| s.neutral).sum::<f64>() / count,
+    }
+}
+
+/// Compute per-entity sentiment for `text`: each entity's score is its raw
+/// scores averaged over the sentence windows it appears in, classified with
+/// the same thresholds as document-level sentiment.
+pub fn analyze_aspects(
+    text: &str,
+    extractor: &EntityExtractor,
+    backend: &dyn SentimentBackend,
+    config: &AnalysisConfig,
+) -> HashMap<String, AspectSentiment> {
+    let windows = sentence_windows(text);
+
+    extractor
+        .extract(text)
+        .into_iter()
+        .filter_map(|entity| {
+            let window_scores: Vec<RawScores> = windows
+                .iter()
+                .filter(|window| window.contains(&entity.text))
+                .map(|window| backend.scores(window))
+                .collect();
+
+            if window_scores.is_empty() {
+                return None;
+            }
+
+            let avg = average(&window_scores);
+            let classification = classify_sentiment(avg.compound, config);
+
+            Some((
+                entity.text,
+                AspectSentiment {
+                    classification,
+                    scores: SentimentScores {
+                        compound: config.include_compound.then_some(avg.compound),
+                        positive: config.include_individual.then_some(avg.positive),
+                        negative: config.include_individual.then_some(avg.negative),
+                        neutral: config.include_individual.then_some(avg.neutral),
+                    },
+                },
+            ))
+        })
+        .collect()
+}
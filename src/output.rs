@@ -0,0 +1,251 @@
+//! Rendering [`SentimentResult`]s as human-readable text or machine-readable
+//! JSON/NDJSON/CSV, so the same analysis can feed either a terminal or a
+//! downstream tool in a pipeline.
+
+use crate::ner::AspectSentiment;
+use crate::{SentimentError, SentimentResult};
+use std::collections::HashMap;
+
+/// Supported `--output`/`output.format` values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn parse(value: &str) -> Result<Self, SentimentError> {
+        match value.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            other => Err(SentimentError::ConfigError(format!(
+                "Unknown output format '{}', expected 'text', 'json', 'ndjson' or 'csv'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Write results to stdout in the requested format.
+///
+/// `numbered` controls the text format only: a single text (e.g. the
+/// `analyze` command) prints the original single-result layout, while a
+/// file of texts prints the original `Line N:` layout.
+pub fn write_results(results: &[SentimentResult], format: OutputFormat, numbered: bool) -> Result<(), SentimentError> {
+    match format {
+        OutputFormat::Text if numbered => write_text_multi(results),
+        OutputFormat::Text => write_text_single(results),
+        OutputFormat::Json => write_json(results)?,
+        OutputFormat::Ndjson => write_ndjson(results)?,
+        OutputFormat::Csv => write_csv(results)?,
+    }
+    Ok(())
+}
+
+fn print_text_fields(result: &SentimentResult) {
+    println!("Text: {}", result.text);
+    println!("Classification: {}", result.classification);
+
+    if let Some(compound) = result.scores.compound {
+        println!("Compound Score: {:.4}", compound);
+    }
+
+    if let Some(pos) = result.scores.positive {
+        println!("Positive: {:.4}", pos);
+    }
+    if let Some(neg) = result.scores.negative {
+        println!("Negative: {:.4}", neg);
+    }
+    if let Some(neu) = result.scores.neutral {
+        println!("Neutral: {:.4}", neu);
+    }
+
+    if let Some(aspects) = &result.aspects {
+        println!("Aspects:");
+        for (entity, sentiment) in aspects {
+            print!("  {}: {}", entity, sentiment.classification);
+            if let Some(compound) = sentiment.scores.compound {
+                print!(" (compound: {:.4})", compound);
+            }
+            println!();
+        }
+    }
+}
+
+fn write_text_single(results: &[SentimentResult]) {
+    println!("\nSentiment Analysis Result:");
+    for result in results {
+        print_text_fields(result);
+    }
+}
+
+fn write_text_multi(results: &[SentimentResult]) {
+    println!("File Analysis Results:");
+    println!("=====================");
+
+    for (i, result) in results.iter().enumerate() {
+        println!("\nLine {}:", i + 1);
+        print_text_fields(result);
+    }
+}
+
+fn write_json(results: &[SentimentResult]) -> Result<(), SentimentError> {
+    println!("{}", serde_json::to_string_pretty(results)?);
+    Ok(())
+}
+
+fn write_ndjson(results: &[SentimentResult]) -> Result<(), SentimentError> {
+    for result in results {
+        println!("{}", serde_json::to_string(result)?);
+    }
+    Ok(())
+}
+
+fn write_csv(results: &[SentimentResult]) -> Result<(), SentimentError> {
+    let mut writer = csv::Writer::from_writer(std::io::stdout());
+    writer.write_record(CSV_HEADER)?;
+
+    for result in results {
+        writer.write_record(csv_record(result))?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn opt_to_string(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.4}", v)).unwrap_or_default()
+}
+
+const CSV_HEADER: [&str; 7] = ["text", "classification", "compound", "positive", "negative", "neutral", "aspects"];
+
+fn csv_record(result: &SentimentResult) -> [String; 7] {
+    [
+        result.text.clone(),
+        result.classification.clone(),
+        opt_to_string(result.scores.compound),
+        opt_to_string(result.scores.positive),
+        opt_to_string(result.scores.negative),
+        opt_to_string(result.scores.neutral),
+        format_aspects(&result.aspects),
+    ]
+}
+
+/// Flatten per-entity aspect sentiment into a single CSV field, e.g.
+/// `battery=Negative(-0.4215); screen=Positive(0.6249)`, sorted by entity
+/// name for stable output (a `HashMap`'s iteration order isn't).
+fn format_aspects(aspects: &Option<HashMap<String, AspectSentiment>>) -> String {
+    let Some(aspects) = aspects else {
+        return String::new();
+    };
+
+    let mut entries: Vec<_> = aspects.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    entries
+        .into_iter()
+        .map(|(entity, sentiment)| format!("{}={}({})", entity, sentiment.classification, opt_to_string(sentiment.scores.compound)))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Writes [`SentimentResult`]s to stdout one at a time, in the requested
+/// format, instead of requiring the full set up front like [`write_results`].
+///
+/// This is what lets `analyze-file` emit each line as soon as it's computed
+/// rather than buffering the whole file in memory, `Json` included: each
+/// result is pretty-printed and emitted as a single array element as soon as
+/// it arrives, with the enclosing `[`/`]` written by [`ResultWriter::new`]
+/// and [`ResultWriter::finish`].
+pub struct ResultWriter {
+    format: OutputFormat,
+    numbered: bool,
+    line_no: usize,
+    csv_writer: Option<csv::Writer<std::io::Stdout>>,
+}
+
+impl ResultWriter {
+    /// `numbered` controls the text format only, exactly like [`write_results`].
+    pub fn new(format: OutputFormat, numbered: bool) -> Result<Self, SentimentError> {
+        match format {
+            OutputFormat::Text if numbered => {
+                println!("File Analysis Results:");
+                println!("=====================");
+            }
+            OutputFormat::Text => println!("\nSentiment Analysis Result:"),
+            OutputFormat::Json => println!("["),
+            _ => {}
+        }
+
+        let csv_writer = match format {
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(std::io::stdout());
+                writer.write_record(CSV_HEADER)?;
+                Some(writer)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            format,
+            numbered,
+            line_no: 0,
+            csv_writer,
+        })
+    }
+
+    /// Write one result, in input order, as soon as it's available.
+    pub fn write(&mut self, result: SentimentResult) -> Result<(), SentimentError> {
+        self.line_no += 1;
+
+        match self.format {
+            OutputFormat::Text => {
+                if self.numbered {
+                    println!("\nLine {}:", self.line_no);
+                }
+                print_text_fields(&result);
+            }
+            OutputFormat::Ndjson => println!("{}", serde_json::to_string(&result)?),
+            OutputFormat::Csv => {
+                let writer = self.csv_writer.as_mut().expect("csv writer initialized for Csv format");
+                writer.write_record(csv_record(&result))?;
+            }
+            OutputFormat::Json => {
+                if self.line_no > 1 {
+                    println!(",");
+                }
+                print!("{}", indent_element(&result)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Flush any buffered state. `Json` closes the array it opened in `new`;
+    /// `Csv` flushes its underlying writer; other formats print as they go.
+    pub fn finish(mut self) -> Result<(), SentimentError> {
+        match self.format {
+            OutputFormat::Json => {
+                if self.line_no > 0 {
+                    println!();
+                }
+                println!("]");
+            }
+            OutputFormat::Csv => self.csv_writer.take().expect("csv writer initialized for Csv format").flush()?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Pretty-print a single result and indent it two spaces, so a run of them
+/// reads as the elements of the same pretty-printed array `write_json` would
+/// produce for the whole slice at once.
+fn indent_element(result: &SentimentResult) -> Result<String, SentimentError> {
+    let pretty = serde_json::to_string_pretty(result)?;
+    Ok(pretty.lines().map(|line| format!("  {}", line)).collect::<Vec<_>>().join("\n"))
+}